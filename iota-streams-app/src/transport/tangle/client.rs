@@ -16,16 +16,17 @@ use smol::block_on;
 
 #[cfg(feature = "async")]
 use iota_streams_core::prelude::Rc;
-#[cfg(feature = "async")]
 use core::cell::RefCell;
 
 use iota::{
     client as iota_client,
+    client::Topic,
     Message, MessageId, MessageBuilder, ClientMiner,
     message::payload::{
         indexation::Indexation,
         Payload
-    }
+    },
+    LedgerInclusionStateDto,
 };
 
 pub use iota::client::bytes_to_trytes;
@@ -36,7 +37,7 @@ use iota_streams_core::{
         ToString,
         Vec,
     },
-    {Errors::*, wrapped_err, try_or, WrappedError, LOCATION_LOG, Result},
+    {Errors::*, try_or, LOCATION_LOG, Result},
 };
 
 use crate::{
@@ -48,8 +49,14 @@ use crate::{
 };
 
 use futures::future::join_all;
+use futures::channel::mpsc::{unbounded, UnboundedReceiver};
+use futures::{stream, Stream, StreamExt};
+use smol::Timer;
+use core::time::Duration;
 use std::boxed::Box;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::str;
+use std::time::Instant;
 
 #[derive(Clone, Copy)]
 pub struct SendTrytesOptions {
@@ -57,6 +64,19 @@ pub struct SendTrytesOptions {
     pub min_weight_magnitude: u8,
     pub local_pow: bool,
     pub threads: usize,
+    /// Maximum total time, in seconds, to spend shepherding a posted message to confirmation
+    /// (reattaching and promoting as the node advises) before the send is reported as failed.
+    pub confirm_wait: u64,
+    /// Maximum size, in bytes, of a single posted indexation message (header included). Payloads
+    /// larger than this are split into ordered fragments posted under the same index.
+    pub chunk_size: usize,
+    /// When set, reads fan out to several pool nodes and only payloads that enough of them agree on
+    /// are returned, guarding against a single lying or lagging node.
+    pub quorum: bool,
+    /// How many healthy nodes to query concurrently when `quorum` is set.
+    pub quorum_size: usize,
+    /// Minimum number of agreeing nodes required to accept a payload under quorum reads.
+    pub quorum_threshold: usize,
 }
 
 impl Default for SendTrytesOptions {
@@ -66,48 +86,228 @@ impl Default for SendTrytesOptions {
             min_weight_magnitude: 14,
             local_pow: true,
             threads: num_cpus::get(),
+            confirm_wait: 40,
+            chunk_size: 8192,
+            quorum: false,
+            quorum_size: 1,
+            quorum_threshold: 1,
         }
     }
 }
 
+/// Number of consecutive failures after which a node is treated as unhealthy and deprioritised.
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+/// How many milestones a node may trail the best-seen index by before it is treated as lagging and
+/// routed to only after its up-to-date peers.
+const MAX_MILESTONE_LAG: u32 = 2;
+
+/// Initial and maximum backoff, in seconds, between confirmation polls.
+const CONFIRM_BACKOFF_START: u64 = 5;
+const CONFIRM_BACKOFF_MAX: u64 = 30;
+
+/// Initial and maximum backoff, in seconds, between subscription reconnect attempts after the
+/// underlying MQTT feed drops, so a flapping node is retried promptly but never hammered.
+const SUBSCRIBE_RECONNECT_START: u64 = 1;
+const SUBSCRIBE_RECONNECT_MAX: u64 = 30;
+
+/// Fixed per-fragment header prepended to each posted chunk: an 8-byte content id shared by every
+/// fragment of one payload, then the fragment index and total fragment count (both `u16`,
+/// big-endian). Receivers use it to regroup and reorder fragments before reconstruction.
+const FRAGMENT_HEADER_LEN: usize = 12;
+
+/// Upper bound on the number of recently completed payloads a subscription remembers for
+/// deduplication, and on the in-flight partial payloads it buffers, so a long-lived stream's
+/// bookkeeping stays bounded instead of growing forever.
+const SUBSCRIBE_DEDUP_CAP: usize = 4096;
+
+/// Deterministic content id for a payload (FNV-1a over the link address, tag and body), so every
+/// fragment of one posted message agrees on it without a shared counter or randomness, while
+/// messages under different indices never collide even when their bodies are byte-identical.
+fn content_id(address: &[u8], tag: &[u8], body: &[u8]) -> u64 {
+    let mut hash = 0xcbf2_9ce4_8422_2325u64;
+    // A length-prefixed separator keeps distinct (address, tag, body) splits from hashing alike.
+    for part in [address, tag, body] {
+        hash ^= part.len() as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        for byte in part {
+            hash ^= *byte as u64;
+            hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+    hash
+}
+
+/// One fragment of a (possibly chunked) payload recovered from a single Tangle message.
+struct Fragment {
+    cid: u64,
+    index: u16,
+    total: u16,
+    data: Vec<u8>,
+}
+
+/// Persistent state threaded through a subscription's stream: the reassembly buffers and dedup
+/// bookkeeping outlive any single MQTT connection, and `install` reopens the feed after a drop so
+/// the reconnect loop can replace `rx` in place.
+struct SubscribeState {
+    link: TangleAddress,
+    install: Box<dyn Fn() -> Result<UnboundedReceiver<Fragment>>>,
+    rx: UnboundedReceiver<Fragment>,
+    groups: HashMap<u64, HashMap<u16, Vec<u8>>>,
+    pending: VecDeque<u64>,
+    done: HashSet<u64>,
+    order: VecDeque<u64>,
+    backoff: u64,
+}
+
+fn parse_fragment(message: &Message) -> Result<Fragment> {
+    if let Some(Payload::Indexation(i)) = message.payload().as_ref() {
+        let framed = i.data();
+        ensure!(framed.len() >= FRAGMENT_HEADER_LEN, "Indexation payload shorter than fragment header");
+        let cid = u64::from_be_bytes(framed[0..8].try_into().unwrap());
+        let index = u16::from_be_bytes(framed[8..10].try_into().unwrap());
+        let total = u16::from_be_bytes(framed[10..12].try_into().unwrap());
+        Ok(Fragment { cid, index, total, data: framed[FRAGMENT_HEADER_LEN..].to_vec() })
+    } else {
+        Err(anyhow!("Message is not a Indexation type"))
+    }
+}
+
+/// Options controlling how messages are received from the Tangle.
+#[derive(Clone, Copy)]
+pub struct RecvOptions {
+    /// When set, only messages whose ledger inclusion state is `Included` are returned;
+    /// unconfirmed and conflicting messages are dropped.
+    pub only_confirmed: bool,
+}
+
+impl Default for RecvOptions {
+    fn default() -> Self {
+        Self { only_confirmed: false }
+    }
+}
+
 fn handle_client_result<T>(result: iota_client::Result<T>) -> Result<T> {
     result.map_err(|err| anyhow!("Failed iota_client: {}", err))
 }
 
+/// A message fetched from the Tangle together with the confirmation metadata the node reported
+/// for it: the timestamp of the milestone that referenced it (0 if still unreferenced) and its
+/// ledger inclusion state, if any.
+struct ConfirmedMessage {
+    message: Message,
+    timestamp: u64,
+    inclusion_state: Option<LedgerInclusionStateDto>,
+}
+
+impl ConfirmedMessage {
+    /// Whether the message was confirmed by the network with an `Included` ledger state.
+    fn is_confirmed(&self) -> bool {
+        matches!(self.inclusion_state, Some(LedgerInclusionStateDto::Included))
+    }
+}
+
 /// Reconstruct Streams Message from bundle. The input bundle is not checked (for validity of
 /// the hash, consistency of indices, etc.). Checked bundles are returned by `bundles_from_trytes`.
-pub fn msg_from_tangle_message<F>(message: &Message, link: &TangleAddress) -> Result<TangleMessage<F>> {
-    if let Payload::Indexation(i) = message.payload().as_ref().unwrap() {
-        let binary = BinaryMessage::new(link.clone(), hex::decode(i.data())?.into());
-    
-        // TODO get timestamp
-        let timestamp: u64 = 0;
-    
-        Ok(TangleMessage { binary, timestamp })
-    } else {
-        Err(anyhow!("Message is not a Indexation type"))
+pub fn msg_from_tangle_message<F>(message: &Message, link: &TangleAddress, timestamp: u64) -> Result<TangleMessage<F>> {
+    let fragment = parse_fragment(message)?;
+    ensure!(fragment.total == 1, "Message is one fragment of a chunked payload; use recv_messages to reassemble");
+    let binary = BinaryMessage::new(link.clone(), fragment.data.into());
+    Ok(TangleMessage { binary, timestamp })
+}
+
+/// Feed one fragment delivered by a subscription into the reassembly buffers, returning a
+/// reassembled `TangleMessage` only when its final fragment completes the payload. Both the
+/// in-flight partial payloads (`groups`/`pending`) and the completed-id dedup window (`done`/
+/// `order`) are capped at `SUBSCRIBE_DEDUP_CAP`, so a long-lived subscription's bookkeeping stays
+/// bounded even when some payloads never fully arrive. This is the push-path counterpart of the
+/// grouping in `async_recv_messages`.
+fn reassemble_event<F>(
+    link: &TangleAddress,
+    groups: &mut HashMap<u64, HashMap<u16, Vec<u8>>>,
+    pending: &mut VecDeque<u64>,
+    done: &mut HashSet<u64>,
+    order: &mut VecDeque<u64>,
+    fragment: Fragment,
+) -> Option<TangleMessage<F>> {
+    if done.contains(&fragment.cid) {
+        return None; // Already emitted this payload.
+    }
+
+    let Fragment { cid, index, total, data } = fragment;
+    let total = total as usize;
+    let newly_seen = !groups.contains_key(&cid);
+    groups.entry(cid).or_default().insert(index, data);
+    if newly_seen {
+        // Track first-seen order so an incomplete payload whose final fragment never arrives is
+        // eventually evicted instead of leaking.
+        pending.push_back(cid);
+        if pending.len() > SUBSCRIBE_DEDUP_CAP {
+            if let Some(old) = pending.pop_front() {
+                groups.remove(&old);
+            }
+        }
+    }
+
+    let by_index = groups.get(&cid)?;
+    let complete = by_index.len() == total && (0..total).all(|i| by_index.contains_key(&(i as u16)));
+    if !complete {
+        return None;
+    }
+
+    let mut body = Vec::new();
+    for i in 0..total {
+        body.extend_from_slice(&by_index[&(i as u16)]);
+    }
+    groups.remove(&cid);
+    if let Some(pos) = pending.iter().position(|&c| c == cid) {
+        pending.remove(pos);
+    }
+
+    // Remember the completed payload, evicting the oldest id once the dedup window is full.
+    if done.insert(cid) {
+        order.push_back(cid);
+        if order.len() > SUBSCRIBE_DEDUP_CAP {
+            if let Some(old) = order.pop_front() {
+                done.remove(&old);
+            }
+        }
     }
+
+    let binary = BinaryMessage::new(link.clone(), body.into());
+    Some(TangleMessage { binary, timestamp: 0 })
 }
 
-async fn get_messages(client: &iota_client::Client, tx_address: &[u8], tx_tag: &[u8]) -> Result<Vec<Message>> {
+async fn get_messages(client: &iota_client::Client, tx_address: &[u8], tx_tag: &[u8]) -> Result<Vec<ConfirmedMessage>> {
     let msg_ids = handle_client_result(client.get_message()
             .index(&hex::encode([tx_address, tx_tag].concat()))
             .await
-        ).unwrap();
-    ensure!(!msg_ids.is_empty(), "Messade ids not found.");
+        )?;
+    // An empty index lookup is a genuine "nothing posted yet", not a node failure, so report it as
+    // an empty success; real client errors above propagate as `Err` for the pool to fail over on.
+    if msg_ids.is_empty() {
+        return Ok(Vec::new());
+    }
 
     let msgs = join_all(
         msg_ids.iter().map(|msg| {
             async move {
-                handle_client_result(client
-                    .get_message()
-                    .data(msg)
-                    .await
-                ).unwrap()
+                let message = handle_client_result(client.get_message().data(msg).await)?;
+                let metadata = handle_client_result(client.get_message().metadata(msg).await)?;
+                // The confirming milestone carries the only trustworthy timestamp; fall back to 0
+                // while the message is still unreferenced.
+                let timestamp = match metadata.referenced_by_milestone_index {
+                    Some(index) => handle_client_result(client.get_milestone(index).await)?.timestamp,
+                    None => 0,
+                };
+                Ok(ConfirmedMessage {
+                    message,
+                    timestamp,
+                    inclusion_state: metadata.ledger_inclusion_state,
+                })
             }
         }
-    )).await;
-    ensure!(!msgs.is_empty(), "Messages not found.");
+    )).await.into_iter().collect::<Result<Vec<_>>>()?;
     Ok(msgs)
 }
 
@@ -118,21 +318,37 @@ fn make_bundle(
     _timestamp: u64,
     trunk: MessageId,
     branch: MessageId,
+    chunk_size: usize,
 ) -> Result<Vec<Message>> {
+    let index = hex::encode([address, tag].concat());
+
+    // Reserve room for the per-fragment header so every posted message stays under `chunk_size`;
+    // short payloads still yield a single fragment with `total == 1`.
+    let data_chunk = chunk_size.saturating_sub(FRAGMENT_HEADER_LEN).max(1);
+    let chunks: Vec<&[u8]> = if body.is_empty() {
+        vec![&body[..]]
+    } else {
+        body.chunks(data_chunk).collect()
+    };
+    let total = chunks.len() as u16;
+    let cid = content_id(address, tag, body);
+
     let mut msgs = Vec::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let mut framed = Vec::with_capacity(FRAGMENT_HEADER_LEN + chunk.len());
+        framed.extend_from_slice(&cid.to_be_bytes());
+        framed.extend_from_slice(&(i as u16).to_be_bytes());
+        framed.extend_from_slice(&total.to_be_bytes());
+        framed.extend_from_slice(chunk);
 
-    dbg!( hex::encode([address, tag].concat()));
-    let payload = Indexation::new(
-        hex::encode([address, tag].concat()), 
-        body).unwrap();
-    //TODO: Multiple messages if payload size is over max. Currently no max decided
-    let msg = MessageBuilder::<ClientMiner>::new()
-        .with_parent1(trunk)
-        .with_parent2(branch)
-        .with_payload(Payload::Indexation(Box::new(payload)))
-        .finish();
-
-    msgs.push(msg.unwrap());
+        let payload = Indexation::new(index.clone(), &framed).unwrap();
+        let msg = MessageBuilder::<ClientMiner>::new()
+            .with_parent1(trunk)
+            .with_parent2(branch)
+            .with_payload(Payload::Indexation(Box::new(payload)))
+            .finish();
+        msgs.push(msg.unwrap());
+    }
     Ok(msgs)
 }
 
@@ -141,6 +357,7 @@ pub fn msg_to_tangle<F>(
     timestamp: u64,
     trunk: MessageId,
     branch: MessageId,
+    chunk_size: usize,
 ) -> Result<Vec<Message>> {
     make_bundle(
         msg.link.appinst.as_ref(),
@@ -149,116 +366,420 @@ pub fn msg_to_tangle<F>(
         timestamp,
         trunk,
         branch,
+        chunk_size,
     )
 }
 
 async fn send_messages(client: &iota_client::Client, _opt: &SendTrytesOptions, msgs: Vec<Message>) -> Result<Vec<MessageId>> {
-    let msgs = join_all(
+    join_all(
         msgs.iter().map(|msg| {
             async move {
-                handle_client_result(client.post_message(msg).await).unwrap()
+                handle_client_result(client.post_message(msg).await)
             }
         }
-    )).await;
-
-    Ok(msgs)
+    )).await.into_iter().collect()
 }
 
-#[derive(Clone, Copy)]
-pub struct SendTrytesOptions {
-    pub depth: u8,
-    pub min_weight_magnitude: u8,
-    pub local_pow: bool,
-    pub threads: usize,
-}
+pub async fn async_send_message_with_options<F>(client: &iota_client::Client, msg: &TangleMessage<F>, opt: &SendTrytesOptions) -> Result<MessageId> {
+    // TODO: Get trunk and branch hashes. Although, `send_trytes` should get these hashes.
+    let tips = client.get_tips().await.unwrap();
+    let messages = msg_to_tangle(&msg.binary, msg.timestamp, tips.0, tips.1, opt.chunk_size)?;
 
-#[cfg(feature = "num_cpus")]
-fn get_num_cpus() -> usize {
-    num_cpus::get()
+    // Post once, then shepherd every posted fragment through the reattach/promote lifecycle rather
+    // than assuming a single post ends up confirmed. For a chunked payload all N fragments must be
+    // confirmed, otherwise the receiver can never reassemble the group.
+    let ids = send_messages(client, opt, messages).await?;
+    ensure!(!ids.is_empty(), "Node accepted no messages");
+    let confirmed = await_confirmation(client, opt, msg, ids).await?;
+    Ok(confirmed[0])
 }
 
-#[cfg(not(feature = "num_cpus"))]
-fn get_num_cpus() -> usize {
-    1_usize
-}
+/// Poll each posted fragment's metadata on a capped exponential backoff, reattaching or promoting
+/// as the node advises, until every fragment is confirmed or `opt.confirm_wait` seconds have
+/// elapsed. Returns the final (post-reattachment) fragment ids.
+async fn await_confirmation<F>(
+    client: &iota_client::Client,
+    opt: &SendTrytesOptions,
+    msg: &TangleMessage<F>,
+    mut msg_ids: Vec<MessageId>,
+) -> Result<Vec<MessageId>> {
+    let mut waited = 0u64;
+    let mut backoff = CONFIRM_BACKOFF_START;
+    while waited < opt.confirm_wait {
+        let mut all_confirmed = true;
+        let mut needs_reattach = false;
+        let mut promote: Vec<MessageId> = Vec::new();
+        for id in &msg_ids {
+            // A just-accepted message's metadata is not always queryable yet; treat a failed read
+            // as "not confirmed yet" and keep waiting rather than aborting the whole send.
+            match client.get_message().metadata(id).await {
+                Ok(metadata) => {
+                    if matches!(metadata.ledger_inclusion_state, Some(LedgerInclusionStateDto::Included)) {
+                        continue;
+                    }
+                    all_confirmed = false;
+                    if metadata.should_reattach.unwrap_or(false) {
+                        needs_reattach = true;
+                    } else if metadata.should_promote.unwrap_or(false) {
+                        promote.push(*id);
+                    }
+                }
+                Err(_) => all_confirmed = false,
+            }
+        }
+        if all_confirmed {
+            return Ok(msg_ids);
+        }
 
-impl Default for SendTrytesOptions {
-    fn default() -> Self {
-        Self {
-            depth: 3,
-            min_weight_magnitude: 14,
-            local_pow: true,
-            threads: get_num_cpus(),
+        if needs_reattach {
+            // Reattachment: rebuild the whole indexation payload against freshly fetched tips and
+            // repost it, tracking the new fragment ids from here on. Duplicate fragments are
+            // collapsed by index on the receive side, so reposting every fragment is safe.
+            let tips = client.get_tips().await.unwrap();
+            let messages = msg_to_tangle(&msg.binary, msg.timestamp, tips.0, tips.1, opt.chunk_size)?;
+            msg_ids = send_messages(client, opt, messages).await?;
+            ensure!(!msg_ids.is_empty(), "Node accepted no messages on reattach");
+        } else {
+            // Promotion: an empty message referencing each stuck fragment plus a fresh tip nudges
+            // the node to select it for approval without changing its contents.
+            for id in promote {
+                let tips = client.get_tips().await.unwrap();
+                let promotion = MessageBuilder::<ClientMiner>::new()
+                    .with_parent1(id)
+                    .with_parent2(tips.0)
+                    .finish()
+                    .map_err(|e| anyhow!("Failed to build promotion message: {}", e))?;
+                send_messages(client, opt, vec![promotion]).await?;
+            }
         }
+
+        // Never sleep past the caller's total budget.
+        let sleep = backoff.min(opt.confirm_wait - waited);
+        Timer::after(Duration::from_secs(sleep)).await;
+        waited += sleep;
+        backoff = (backoff * 2).min(CONFIRM_BACKOFF_MAX);
     }
+    Err(anyhow!("Message(s) not confirmed within {}s", opt.confirm_wait))
 }
 
-fn handle_client_result<T>(result: iota_client::Result<T>) -> Result<T> {
-    result.map_err(|err| wrapped_err!(ClientOperationFailure, WrappedError(err)))
-}
+pub async fn async_recv_messages<F>(client: &iota_client::Client, link: &TangleAddress, opt: &RecvOptions) -> Result<Vec<TangleMessage<F>>> {
+    let tx_address = link.appinst.as_ref();
+    let tx_tag = link.msgid.as_ref();
+    // Propagate a genuine node/query failure so the pool can fail over and mark the node unhealthy;
+    // a link with nothing posted yet comes back as an empty (successful) vector, not an error.
+    let msgs = get_messages(client, tx_address, tx_tag).await?;
 
-async fn get_bundles(client: &iota_client::Client, tx_address: Address, tx_tag: Tag) -> Result<Vec<Transaction>> {
-    let find_bundles = handle_client_result(
-        client.find_transactions()
-            .tags(&vec![tx_tag][..])
-            .addresses(&vec![tx_address][..])
-            .send()
-            .await,
-    )?;
-    try_or!(!find_bundles.hashes.is_empty(), HashNotFound)?;
+    // Group fragments by content id, deduplicating by fragment index so that a re-posted message
+    // (e.g. a reattachment) contributing a second copy of an index cannot inflate the group and
+    // block reassembly. The newest confirming milestone timestamp for each index is kept.
+    let mut groups: HashMap<u64, HashMap<u16, (Fragment, u64)>> = HashMap::new();
+    for m in &msgs {
+        if opt.only_confirmed && !m.is_confirmed() {
+            continue;
+        }
+        if let Ok(fragment) = parse_fragment(&m.message) {
+            let by_index = groups.entry(fragment.cid).or_default();
+            match by_index.get(&fragment.index) {
+                Some(existing) if m.timestamp <= existing.1 => {} // Keep the copy we already have.
+                _ => {
+                    by_index.insert(fragment.index, (fragment, m.timestamp));
+                }
+            }
+        }
+    }
 
-    let get_resp = handle_client_result(client.get_trytes(&find_bundles.hashes).await)?;
-    try_or!(!get_resp.trytes.is_empty(), TransactionContentsNotFound)?;
-    Ok(get_resp.trytes)
+    let mut out = Vec::new();
+    for (_cid, by_index) in groups {
+        let mut parts: Vec<(Fragment, u64)> = by_index.into_iter().map(|(_, v)| v).collect();
+        let total = parts[0].0.total as usize;
+        parts.sort_by_key(|p| p.0.index);
+        // Only surface a payload once every fragment has arrived in a gap-free sequence; an
+        // incomplete group is skipped and picked up on a later poll.
+        let complete = parts.len() == total
+            && parts.iter().enumerate().all(|(i, p)| p.0.index as usize == i);
+        if !complete {
+            continue;
+        }
+
+        let mut body = Vec::new();
+        for (fragment, _) in &parts {
+            body.extend_from_slice(&fragment.data);
+        }
+        let timestamp = parts.iter().map(|p| p.1).max().unwrap_or(0);
+        let binary = BinaryMessage::new(link.clone(), body.into());
+        out.push(TangleMessage { binary, timestamp });
+    }
+    Ok(out)
 }
 
-async fn send_trytes(client: &iota_client::Client, opt: &SendTrytesOptions, txs: Vec<Transaction>) -> Result<Vec<Transaction>> {
-    let attached_txs = handle_client_result(
-        client.send_trytes()
-            .min_weight_magnitude(opt.min_weight_magnitude)
-            .depth(opt.depth)
-            .trytes(txs)
-            .send()
-            .await,
-    )?;
-    Ok(attached_txs)
+#[cfg(not(feature = "async"))]
+pub fn sync_send_message_with_options<F>(client: &iota_client::Client, msg: &TangleMessage<F>, opt: &SendTrytesOptions) -> Result<MessageId> {
+    block_on(async_send_message_with_options(client, msg, opt))
 }
 
-pub async fn async_send_message_with_options<F>(client: &iota_client::Client, msg: &TangleMessage<F>, opt: &SendTrytesOptions) -> Result<()> {
-    // TODO: Get trunk and branch hashes. Although, `send_trytes` should get these hashes.
-    let tips = client.get_tips().await.unwrap();
-    let messages = msg_to_tangle(&msg.binary, msg.timestamp, tips.0, tips.1)?;
+#[cfg(not(feature = "async"))]
+pub fn sync_recv_messages<F>(client: &iota_client::Client, link: &TangleAddress, opt: &RecvOptions) -> Result<Vec<TangleMessage<F>>> {
+    block_on(async_recv_messages(client, link, opt))
+}
 
-    // Ignore attached transactions.
-    send_messages(client, opt, messages).await?;
-    Ok(())
+/// Per-node health bookkeeping for the pool: the node's own `iota_client::Client`, the latency of
+/// its last successful ping, how many operations have failed against it back to back (used to
+/// deprioritise and eventually stop routing to it), and the latest milestone index it reported
+/// (so a lagging node can be spotted next to its peers).
+struct NodeHealth {
+    url: String,
+    client: iota_client::Client,
+    last_latency: Duration,
+    consecutive_failures: u32,
+    last_milestone_index: u32,
 }
 
-pub async fn async_recv_messages<F>(client: &iota_client::Client, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
-    let tx_address = link.appinst.as_ref();
-    let tx_tag = link.msgid.as_ref();
-    match get_messages(client, tx_address, tx_tag).await {
-        Ok(txs) => Ok(txs.iter()
-            .map(|b| msg_from_tangle_message(b, link).unwrap())
-            .collect()),
-        Err(_) => Ok(Vec::new()), // Just ignore the error?
+impl NodeHealth {
+    fn new(url: &str) -> Result<Self> {
+        let client = iota_client::ClientBuilder::new()
+            .with_node(url)
+            .map_err(|e| anyhow!("Invalid node url {}: {}", url, e))?
+            .finish()
+            .map_err(|e| anyhow!("Failed to build node {}: {}", url, e))?;
+        Ok(Self {
+            url: url.to_string(),
+            client,
+            last_latency: Duration::from_secs(0),
+            consecutive_failures: 0,
+            last_milestone_index: 0,
+        })
+    }
+
+    /// Whether the node is still trusted for routing; a node is sidelined once it has failed
+    /// `MAX_CONSECUTIVE_FAILURES` operations in a row without a success in between.
+    fn is_healthy(&self) -> bool {
+        self.consecutive_failures < MAX_CONSECUTIVE_FAILURES
+    }
+
+    fn record_success(&mut self, latency: Duration) {
+        self.last_latency = latency;
+        self.consecutive_failures = 0;
+    }
+
+    fn record_failure(&mut self) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
     }
 }
 
-#[cfg(not(feature = "async"))]
-pub fn sync_send_message_with_options<F>(client: &iota_client::Client, msg: &TangleMessage<F>, opt: &SendTrytesOptions) -> Result<()> {
-    block_on(async_send_message_with_options(client, msg, opt))
+/// A set of Tangle nodes with per-node health tracking. Sends and reads are routed to the healthy
+/// nodes, lowest-latency first, with automatic failover to the next node on error; reads can also
+/// require a quorum of agreeing nodes (see `SendTrytesOptions::quorum`). A pool built from a single
+/// url behaves exactly like the old single-node client, so the existing constructors keep working.
+pub struct NodePool {
+    nodes: Vec<RefCell<NodeHealth>>,
 }
 
-#[cfg(not(feature = "async"))]
-pub fn sync_recv_messages<F>(client: &iota_client::Client, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
-    block_on(async_recv_messages(client, link))
+impl NodePool {
+    fn new(url: &str) -> Result<Self> {
+        Ok(Self { nodes: vec![RefCell::new(NodeHealth::new(url)?)] })
+    }
+
+    fn add_node(&mut self, url: &str) -> Result<bool> {
+        if self.nodes.iter().any(|n| n.borrow().url == url) {
+            return Ok(false);
+        }
+        self.nodes.push(RefCell::new(NodeHealth::new(url)?));
+        Ok(true)
+    }
+
+    /// A node to stand in for the whole pool when a single client is needed (e.g. opening the
+    /// MQTT subscription); the node `healthy_order` ranks first (an up-to-date, lowest-latency
+    /// healthy node, or, when none are healthy, the best of all nodes ranked the same way).
+    fn representative(&self) -> core::cell::Ref<'_, NodeHealth> {
+        let i = self.healthy_order().into_iter().next().unwrap_or(0);
+        self.nodes[i].borrow()
+    }
+
+    /// Ping every node, refreshing its latency and last-seen milestone index and resetting or
+    /// bumping its failure count. Call periodically to keep the routing order fresh.
+    pub async fn health_check(&self) {
+        for node in &self.nodes {
+            let start = Instant::now();
+            let info = {
+                let n = node.borrow();
+                n.client.get_info().await
+            };
+            let mut n = node.borrow_mut();
+            match info {
+                Ok(info) => {
+                    n.record_success(start.elapsed());
+                    n.last_milestone_index = info.nodeinfo.latest_milestone_index;
+                }
+                Err(_) => n.record_failure(),
+            }
+        }
+    }
+
+    /// Indices of the healthy nodes, up-to-date nodes first and within each group lowest-latency
+    /// first. A node trailing the best-seen milestone index by more than `MAX_MILESTONE_LAG` is
+    /// treated as lagging and routed to only once its current peers are exhausted. Falls back to
+    /// every node when none are currently marked healthy, so the pool never goes completely dark
+    /// between health checks.
+    fn healthy_order(&self) -> Vec<usize> {
+        let best_milestone = self
+            .nodes
+            .iter()
+            .map(|n| n.borrow().last_milestone_index)
+            .max()
+            .unwrap_or(0);
+        let mut order: Vec<usize> = (0..self.nodes.len())
+            .filter(|&i| self.nodes[i].borrow().is_healthy())
+            .collect();
+        if order.is_empty() {
+            order = (0..self.nodes.len()).collect();
+        }
+        order.sort_by_key(|&i| {
+            let n = self.nodes[i].borrow();
+            let lagging = best_milestone.saturating_sub(n.last_milestone_index) > MAX_MILESTONE_LAG;
+            (lagging, n.last_latency)
+        });
+        order
+    }
+
+    /// Post a message, trying healthy nodes in order and failing over to the next one on error.
+    async fn send_message<F>(&self, msg: &TangleMessage<F>, opt: &SendTrytesOptions) -> Result<MessageId> {
+        let mut last_err = anyhow!("No nodes available to send message");
+        for i in self.healthy_order() {
+            let start = Instant::now();
+            let result = {
+                let node = self.nodes[i].borrow();
+                async_send_message_with_options(&node.client, msg, opt).await
+            };
+            match result {
+                Ok(id) => {
+                    self.nodes[i].borrow_mut().record_success(start.elapsed());
+                    return Ok(id);
+                }
+                Err(e) => {
+                    self.nodes[i].borrow_mut().record_failure();
+                    last_err = e;
+                }
+            }
+        }
+        Err(last_err)
+    }
+
+    /// Receive messages for a link. In quorum mode the read fans out to several nodes and only
+    /// payloads enough of them agree on are returned; otherwise a single healthy node is used with
+    /// failover to the next on error.
+    async fn recv_messages<F>(
+        &self,
+        link: &TangleAddress,
+        send_opt: &SendTrytesOptions,
+        recv_opt: &RecvOptions,
+    ) -> Result<Vec<TangleMessage<F>>> {
+        if send_opt.quorum {
+            return self.quorum_recv(link, send_opt, recv_opt).await;
+        }
+
+        let mut last_err = anyhow!("No nodes available to receive messages");
+        for i in self.healthy_order() {
+            let start = Instant::now();
+            let result = {
+                let node = self.nodes[i].borrow();
+                async_recv_messages(&node.client, link, recv_opt).await
+            };
+            match result {
+                Ok(msgs) => {
+                    self.nodes[i].borrow_mut().record_success(start.elapsed());
+                    return Ok(msgs);
+                }
+                Err(e) => {
+                    self.nodes[i].borrow_mut().record_failure();
+                    last_err = e;
+                }
+            }
+        }
+        Err(last_err)
+    }
+
+    /// Fan a read out to up to `quorum_size` healthy nodes concurrently and keep only the payloads
+    /// that at least `quorum_threshold` of them return, so a single lying or lagging node cannot by
+    /// itself inject or withhold a message.
+    async fn quorum_recv<F>(
+        &self,
+        link: &TangleAddress,
+        send_opt: &SendTrytesOptions,
+        recv_opt: &RecvOptions,
+    ) -> Result<Vec<TangleMessage<F>>> {
+        let selected: Vec<usize> = self
+            .healthy_order()
+            .into_iter()
+            .take(send_opt.quorum_size.max(1))
+            .collect();
+        ensure!(!selected.is_empty(), "No nodes available for quorum read");
+
+        let results = join_all(selected.iter().map(|&i| async move {
+            let start = Instant::now();
+            let node = self.nodes[i].borrow();
+            let msgs = async_recv_messages::<F>(&node.client, link, recv_opt).await;
+            (i, start.elapsed(), msgs)
+        }))
+        .await;
+
+        // Tally how many distinct nodes returned each payload; accept one once it clears the
+        // agreement threshold.
+        let mut tally: HashMap<Vec<u8>, (usize, TangleMessage<F>)> = HashMap::new();
+        let mut answered = 0usize;
+        for (i, latency, result) in results {
+            match result {
+                Ok(msgs) => {
+                    answered += 1;
+                    self.nodes[i].borrow_mut().record_success(latency);
+                    let mut counted: HashSet<Vec<u8>> = HashSet::new();
+                    for msg in msgs {
+                        let key = msg.binary.body.bytes.clone();
+                        if !counted.insert(key.clone()) {
+                            continue; // One node's duplicate must not count twice toward quorum.
+                        }
+                        match tally.get_mut(&key) {
+                            Some(entry) => {
+                                entry.0 += 1;
+                                // Keep the copy with the newest confirming-milestone timestamp
+                                // rather than whichever node happened to answer first.
+                                if msg.timestamp > entry.1.timestamp {
+                                    entry.1 = msg;
+                                }
+                            }
+                            None => {
+                                tally.insert(key, (1, msg));
+                            }
+                        }
+                    }
+                }
+                Err(_) => self.nodes[i].borrow_mut().record_failure(),
+            }
+        }
+
+        // The quorum must not be weakened to however many nodes happened to reply: if fewer nodes
+        // answered than the caller's threshold, the guarantee cannot be met, so fail rather than
+        // accept a payload a single lying or lagging node could have supplied.
+        let threshold = send_opt.quorum_threshold.max(1);
+        ensure!(
+            answered >= threshold,
+            "Quorum not met: only {} of {} queried nodes answered, need {}",
+            answered,
+            selected.len(),
+            threshold
+        );
+        Ok(tally
+            .into_iter()
+            .filter(|(_, (count, _))| *count >= threshold)
+            .map(|(_, (_, msg))| msg)
+            .collect())
+    }
 }
 
 /// Stub type for iota_client::Client.  Removed: Copy, Default, Clone
 pub struct Client {
     send_opt: SendTrytesOptions,
-    client: iota_client::Client,
+    recv_opt: RecvOptions,
+    pool: NodePool,
 }
 
 impl Default for Client {
@@ -266,7 +787,8 @@ impl Default for Client {
     fn default() -> Self {
         Self {
             send_opt: SendTrytesOptions::default(),
-            client: iota_client::ClientBuilder::new().with_node("http://localhost:14265").unwrap().finish().unwrap()
+            recv_opt: RecvOptions::default(),
+            pool: NodePool::new("http://localhost:14265").unwrap()
         }
     }
 }
@@ -276,7 +798,14 @@ impl Client {
     pub fn new(options: SendTrytesOptions, client: iota_client::Client) -> Self {
         Self {
             send_opt: options,
-            client: client
+            recv_opt: RecvOptions::default(),
+            pool: NodePool { nodes: vec![RefCell::new(NodeHealth {
+                url: String::new(),
+                client,
+                last_latency: Duration::from_secs(0),
+                consecutive_failures: 0,
+                last_milestone_index: 0,
+            })] }
         }
     }
 
@@ -284,14 +813,117 @@ impl Client {
     pub fn new_from_url(url: &str) -> Self {
         Self {
             send_opt: SendTrytesOptions::default(),
-            client: iota_client::ClientBuilder::new().with_node(url).unwrap().finish().unwrap()
+            recv_opt: RecvOptions::default(),
+            pool: NodePool::new(url).unwrap()
         }
     }
 
     pub fn add_node(&mut self, url: &str) -> Result<bool> {
-        self.client.add_node(url).map_err(|e|
-            wrapped_err!(ClientOperationFailure, WrappedError(e))
-        )
+        self.pool.add_node(url)
+    }
+
+    /// Ping every node in the pool, refreshing the health data that drives send/receive routing
+    /// and quorum node selection. Intended to be called on a timer by long-lived consumers.
+    pub async fn health_check(&self) {
+        self.pool.health_check().await
+    }
+
+    /// Subscribe to the node's MQTT feed for this link's indexation topic, yielding each new
+    /// `TangleMessage` as it is gossiped instead of repeatedly polling `recv_messages`.
+    ///
+    /// Incoming events are reassembled through the same fragment logic as `recv_messages`: a
+    /// chunked payload posted as several fragments is buffered by content id and only emitted once
+    /// every fragment has arrived, so the stream surfaces whole messages rather than raw fragments.
+    /// Completed payloads are deduplicated by content id (bounded to the most recent
+    /// `SUBSCRIBE_DEDUP_CAP`), so a reconnect-and-backfill never emits the same message twice. The
+    /// returned `Stream` resubscribes on a capped backoff whenever the feed drops, so it does not end
+    /// on its own; consumers drive it with `while let Some(msg) = stream.next().await`.
+    pub fn subscribe<F>(&self, link: &TangleAddress) -> Result<impl Stream<Item = Result<TangleMessage<F>>>>
+    where
+        F: 'static,
+    {
+        let index = hex::encode([link.appinst.as_ref(), link.msgid.as_ref()].concat());
+        let topic = Topic::new(format!("messages/indexation/{}", index))
+            .map_err(|e| anyhow!("Invalid subscription topic: {}", e))?;
+
+        // Clone the client so the reconnect loop outlives the borrow of the representative node; the
+        // MQTT client is a cheap handle, so re-subscribing on it is the same operation as the first
+        // subscribe. Installing a fresh callback into a new channel is how we recover after a drop.
+        let client = self.pool.representative().client.clone();
+        let install = move || {
+            // Tear the previous subscription down before re-subscribing so a flapping node does not
+            // accumulate a fresh callback per reconnect; a first-time unsubscribe on a topic that
+            // was never subscribed is a harmless no-op.
+            let _ = client.subscriber().with_topic(topic.clone()).unsubscribe();
+            let (tx, rx) = unbounded();
+            let subscribed = client
+                .subscriber()
+                .with_topic(topic.clone())
+                .subscribe(move |event| {
+                    // Parse each event into a fragment on the MQTT callback; undecodable or
+                    // unrelated messages on the topic are dropped, exactly as the polling path
+                    // ignores them. The receiver side buffers, reorders and reassembles.
+                    if let Ok(message) = Message::unpack(&mut &event.payload[..]) {
+                        if let Ok(fragment) = parse_fragment(&message) {
+                            let _ = tx.unbounded_send(fragment);
+                        }
+                    }
+                })
+                .map_err(|e| anyhow!("Failed to subscribe: {}", e));
+            subscribed.map(|_| rx)
+        };
+
+        // Open the first subscription eagerly so construction errors surface to the caller rather
+        // than being swallowed inside the stream.
+        let rx = install()?;
+
+        let link = link.clone();
+        // Reassembly and dedup bookkeeping lives in the stream state, not per-subscription, so it
+        // survives a reconnect: fragments replayed by the node after we re-subscribe are regrouped
+        // against the same partial payloads and completed payloads are suppressed by content id.
+        let state = SubscribeState {
+            link,
+            install: Box::new(install),
+            rx,
+            groups: HashMap::new(),
+            pending: VecDeque::new(),
+            done: HashSet::new(),
+            order: VecDeque::new(),
+            backoff: SUBSCRIBE_RECONNECT_START,
+        };
+
+        let stream = stream::unfold(state, |mut state| async move {
+            loop {
+                match state.rx.next().await {
+                    Some(fragment) => {
+                        // A fresh fragment resets the reconnect backoff; the feed is healthy again.
+                        state.backoff = SUBSCRIBE_RECONNECT_START;
+                        if let Some(msg) = reassemble_event(
+                            &state.link,
+                            &mut state.groups,
+                            &mut state.pending,
+                            &mut state.done,
+                            &mut state.order,
+                            fragment,
+                        ) {
+                            return Some((Ok(msg), state));
+                        }
+                    }
+                    // The channel closing means the MQTT feed dropped: reconnect on a capped
+                    // backoff and keep yielding, rather than silently ending the stream.
+                    None => {
+                        Timer::after(Duration::from_secs(state.backoff)).await;
+                        state.backoff = (state.backoff * 2).min(SUBSCRIBE_RECONNECT_MAX);
+                        match (state.install)() {
+                            Ok(rx) => state.rx = rx,
+                            // Resubscribe failed; loop will back off again on the next closed read.
+                            Err(_) => continue,
+                        }
+                    }
+                }
+            }
+        });
+        Ok(stream)
     }
 }
 
@@ -304,21 +936,25 @@ impl TransportOptions for Client {
         self.send_opt = opt;
     }
 
-    type RecvOptions = ();
-    fn get_recv_options(&self) -> () {}
-    fn set_recv_options(&mut self, _opt: ()) {}
+    type RecvOptions = RecvOptions;
+    fn get_recv_options(&self) -> RecvOptions {
+        self.recv_opt
+    }
+    fn set_recv_options(&mut self, opt: RecvOptions) {
+        self.recv_opt = opt;
+    }
 }
 
 #[cfg(not(feature = "async"))]
 impl<F> Transport<TangleAddress, TangleMessage<F>> for Client {
     /// Send a Streams message over the Tangle with the current timestamp and default SendTrytesOptions.
     fn send_message(&mut self, msg: &TangleMessage<F>) -> Result<()> {
-        sync_send_message_with_options(&self.client, msg, &self.send_opt)
+        block_on(self.pool.send_message(msg, &self.send_opt)).map(|_| ())
     }
 
     /// Receive a message.
     fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
-        sync_recv_messages(&self.client, link)
+        block_on(self.pool.recv_messages(link, &self.send_opt, &self.recv_opt))
     }
 }
 
@@ -330,12 +966,12 @@ where
 {
     /// Send a Streams message over the Tangle with the current timestamp and default SendTrytesOptions.
     async fn send_message(&mut self, msg: &TangleMessage<F>) -> Result<()> {
-        async_send_message_with_options(&self.client, msg, &self.send_opt).await
+        self.pool.send_message(msg, &self.send_opt).await.map(|_| ())
     }
 
     /// Receive a message.
     async fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
-        async_recv_messages(&self.client, link).await
+        self.pool.recv_messages(link, &self.send_opt, &self.recv_opt).await
     }
 
     async fn recv_message(&mut self, link: &TangleAddress) -> Result<TangleMessage<F>> {
@@ -359,7 +995,7 @@ where
     /// Send a Streams message over the Tangle with the current timestamp and default SendTrytesOptions.
     async fn send_message(&mut self, msg: &TangleMessage<F>) -> Result<()> {
         match (&*self).try_borrow_mut() {
-            Ok(mut tsp) => async_send_message_with_options(&tsp.client, msg, &tsp.send_opt).await,
+            Ok(mut tsp) => tsp.pool.send_message(msg, &tsp.send_opt).await.map(|_| ()),
             Err(_err) => err!(TransportNotAvailable),
         }
     }
@@ -367,7 +1003,7 @@ where
     /// Receive a message.
     async fn recv_messages(&mut self, link: &TangleAddress) -> Result<Vec<TangleMessage<F>>> {
         match (&*self).try_borrow_mut() {
-            Ok(mut tsp) => async_recv_messages(&tsp.client, link).await,
+            Ok(mut tsp) => tsp.pool.recv_messages(link, &tsp.send_opt, &tsp.recv_opt).await,
             Err(err) => err!(TransportNotAvailable),
         }
     }
@@ -375,7 +1011,7 @@ where
     async fn recv_message(&mut self, link: &TangleAddress) -> Result<TangleMessage<F>> {
         match (&*self).try_borrow_mut() {
             Ok(mut tsp) => {
-                let mut msgs = async_recv_messages(&tsp.client, link).await?;
+                let mut msgs = tsp.pool.recv_messages(link, &tsp.send_opt, &tsp.recv_opt).await?;
                 if let Some(msg) = msgs.pop() {
                     try_or!(msgs.is_empty(), MessageNotUnique(link.msgid.to_string()));
                     Ok(msg)